@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+
+use crate::request::{
+    read_chunked_body, read_content_length_body, timed_read, RequestError, DEFAULT_MAX_BODY_SIZE,
+    DEFAULT_READ_TIMEOUT, MAX_HTTP_MESSAGE_HEADER_SIZE,
+};
+use crate::response::{HTTPResponse, ResponseBody};
+
+const MAX_RESPONSE_HEADERS: usize = 64;
+
+/// Errors that can occur while sending a request and reading back a
+/// response.
+#[derive(Debug)]
+pub enum ClientError {
+    /// The URI wasn't a `http://host[:port]/path`-shaped absolute URI.
+    InvalidUri(String),
+    Io(std::io::Error),
+    /// The response's status line or headers couldn't be parsed.
+    Malformed(String),
+    /// Reading the response failed, e.g. a read timed out, or the body was
+    /// truncated or exceeded the maximum body size.
+    Response(RequestError),
+}
+
+impl From<std::io::Error> for ClientError {
+    fn from(e: std::io::Error) -> Self {
+        ClientError::Io(e)
+    }
+}
+
+impl From<httparse::Error> for ClientError {
+    fn from(e: httparse::Error) -> Self {
+        ClientError::Malformed(e.to_string())
+    }
+}
+
+impl From<RequestError> for ClientError {
+    fn from(e: RequestError) -> Self {
+        ClientError::Response(e)
+    }
+}
+
+/// Splits an absolute `http://host[:port]/path` URI into its authority
+/// (`host:port`, with a default port of 80) and path (`/` if none is
+/// given).
+fn split_uri(uri: &str) -> Result<(String, String), ClientError> {
+    let without_scheme = uri
+        .strip_prefix("http://")
+        .ok_or_else(|| ClientError::InvalidUri(format!("unsupported scheme in {uri}")))?;
+    let (authority, path) = match without_scheme.find('/') {
+        Some(index) => (&without_scheme[..index], &without_scheme[index..]),
+        None => (without_scheme, "/"),
+    };
+    if authority.is_empty() {
+        return Err(ClientError::InvalidUri(format!("missing host in {uri}")));
+    }
+    let authority = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{authority}:80")
+    };
+    Ok((authority, path.to_string()))
+}
+
+async fn connect(uri: &str) -> Result<(TcpStream, String, String), ClientError> {
+    let (authority, path) = split_uri(uri)?;
+    let socket = TcpStream::connect(&authority).await?;
+    Ok((socket, authority, path))
+}
+
+fn serialize_request(
+    method: &str,
+    path: &str,
+    host: &str,
+    headers: &HashMap<String, String>,
+    body: &[u8],
+) -> Vec<u8> {
+    let mut serialized = format!("{method} {path} HTTP/1.1\r\n").into_bytes();
+    serialized.extend_from_slice(format!("Host: {host}\r\n").as_bytes());
+    serialized.extend_from_slice(format!("Content-Length: {}\r\n", body.len()).as_bytes());
+    for (name, value) in headers.iter() {
+        serialized.extend_from_slice(format!("{name}: {value}\r\n").as_bytes());
+    }
+    serialized.extend_from_slice(b"\r\n");
+    serialized.extend_from_slice(body);
+    serialized
+}
+
+/// Reads from `socket`, growing `buf`, until `httparse` can parse a complete
+/// status line and header section, enforcing `MAX_HTTP_MESSAGE_HEADER_SIZE`.
+/// Returns the parsed response (with an empty body) along with any bytes
+/// read past the header terminator, since those belong to the body.
+async fn read_response_head(socket: &mut TcpStream) -> Result<(HTTPResponse, Vec<u8>), ClientError> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 512];
+    loop {
+        let mut header_storage = [httparse::EMPTY_HEADER; MAX_RESPONSE_HEADERS];
+        let mut parsed = httparse::Response::new(&mut header_storage);
+        match parsed.parse(&buf)? {
+            httparse::Status::Complete(offset) => {
+                let status_code = parsed
+                    .code
+                    .ok_or_else(|| ClientError::Malformed("missing status code".to_string()))?;
+                let reason_phrase = parsed.reason.unwrap_or("").to_string();
+                let mut headers = HashMap::new();
+                for header in parsed.headers.iter() {
+                    let value = std::str::from_utf8(header.value)
+                        .map_err(|e| ClientError::Malformed(e.to_string()))?
+                        .trim()
+                        .to_string();
+                    headers.insert(header.name.to_ascii_lowercase(), value);
+                }
+                let leftover = buf.split_off(offset);
+                return Ok((
+                    HTTPResponse {
+                        http_version: "HTTP/1.1",
+                        status_code,
+                        reason_phrase,
+                        headers,
+                        body: ResponseBody::Buffered(Vec::new()),
+                    },
+                    leftover,
+                ));
+            }
+            httparse::Status::Partial => {
+                if buf.len() > MAX_HTTP_MESSAGE_HEADER_SIZE {
+                    return Err(ClientError::Malformed(
+                        "response headers too large".to_string(),
+                    ));
+                }
+                let n = timed_read(socket, &mut chunk, DEFAULT_READ_TIMEOUT)
+                    .await
+                    .map_err(ClientError::Response)?;
+                if n == 0 {
+                    return Err(ClientError::Malformed(
+                        "connection closed mid-headers".to_string(),
+                    ));
+                }
+                buf.extend_from_slice(&chunk[0..n]);
+            }
+        }
+    }
+}
+
+/// Reads a full HTTP response from `socket`, honoring `Content-Length` and
+/// `Transfer-Encoding: chunked`.
+async fn read_response(socket: &mut TcpStream) -> Result<HTTPResponse, ClientError> {
+    let (mut response, leftover) = read_response_head(socket).await?;
+
+    let is_chunked = response
+        .headers
+        .get("transfer-encoding")
+        .map(|v| v.eq_ignore_ascii_case("chunked"))
+        .unwrap_or(false);
+    let content_length: Option<usize> = response
+        .headers
+        .get("content-length")
+        .and_then(|v| v.trim().parse().ok());
+
+    let body = if is_chunked {
+        let (body, _leftover) =
+            read_chunked_body(socket, leftover, DEFAULT_MAX_BODY_SIZE, DEFAULT_READ_TIMEOUT).await?;
+        body
+    } else if let Some(len) = content_length {
+        let (body, _leftover) = read_content_length_body(
+            socket,
+            leftover,
+            len,
+            DEFAULT_MAX_BODY_SIZE,
+            DEFAULT_READ_TIMEOUT,
+        )
+        .await?;
+        body
+    } else {
+        Vec::new()
+    };
+    response.body = ResponseBody::Buffered(body);
+
+    Ok(response)
+}
+
+/// Sends a `method` request to `uri` with the given headers and body, and
+/// waits for the full response.
+pub async fn send(
+    method: &str,
+    uri: &str,
+    headers: HashMap<String, String>,
+    body: &[u8],
+) -> Result<HTTPResponse, ClientError> {
+    let (mut socket, authority, path) = connect(uri).await?;
+    let host = authority
+        .rsplit_once(':')
+        .map(|(host, _port)| host)
+        .unwrap_or(&authority);
+    let request = serialize_request(method, &path, host, &headers, body);
+    socket.write_all(&request).await?;
+    read_response(&mut socket).await
+}
+
+/// Sends a `GET` request to `uri`.
+pub async fn get(uri: &str) -> Result<HTTPResponse, ClientError> {
+    send("GET", uri, HashMap::new(), &[]).await
+}
+
+/// Sends a `POST` request to `uri` with `body`.
+pub async fn post(uri: &str, body: &[u8]) -> Result<HTTPResponse, ClientError> {
+    send("POST", uri, HashMap::new(), body).await
+}
@@ -0,0 +1,11 @@
+pub mod client;
+pub mod request;
+pub mod response;
+pub mod router;
+pub mod server;
+
+pub use client::{get, post, ClientError};
+pub use request::HTTPRequest;
+pub use response::{HTTPResponse, HTTPResponseBuilder};
+pub use router::Router;
+pub use server::Server;
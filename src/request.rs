@@ -0,0 +1,484 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+
+/// Maximum size, in bytes, of the request line plus headers. Requests whose
+/// header section grows past this without a terminating blank line are
+/// rejected with `431 Request Header Fields Too Large` instead of being read
+/// forever.
+pub const MAX_HTTP_MESSAGE_HEADER_SIZE: usize = 8 * 1024;
+
+/// Default cap on the size of a request body (`Content-Length` or decoded
+/// chunked total). Requests over this are rejected with `413 Payload Too
+/// Large`.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Default timeout for a single read once a request has started arriving.
+/// Doesn't apply to the idle wait between kept-alive requests; see
+/// `DEFAULT_TIME_TO_FIRST_BYTE_TIMEOUT` in `server` for that.
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum number of headers `httparse` will parse out of a single request.
+const MAX_HEADERS: usize = 64;
+
+/// A parsed HTTP request. Header names are normalized to lowercase and
+/// repeated headers (`Set-Cookie`, `Forwarded`, ...) are kept as a `Vec`
+/// instead of being silently overwritten. `path_params` is filled in by the
+/// router from any `:name` segments in the matched route pattern.
+#[derive(Debug)]
+pub struct HTTPRequest {
+    pub method: String,
+    pub path: String,
+    pub version: String,
+    pub headers: HashMap<String, Vec<String>>,
+    pub body: Vec<u8>,
+    pub path_params: HashMap<String, String>,
+}
+
+impl HTTPRequest {
+    /// Returns all values for `name`, matched case-insensitively, in the
+    /// order they appeared on the wire.
+    pub fn get_all(&self, name: &str) -> &[String] {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Returns the first value for `name`, matched case-insensitively.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.get_all(name).first().map(String::as_str)
+    }
+
+    /// The parsed `Content-Length`, if present and valid.
+    pub fn content_length(&self) -> Option<usize> {
+        self.get("Content-Length")?.trim().parse().ok()
+    }
+
+    /// Whether `Transfer-Encoding: chunked` is present.
+    pub fn is_chunked(&self) -> bool {
+        self.get("Transfer-Encoding")
+            .map(|v| v.eq_ignore_ascii_case("chunked"))
+            .unwrap_or(false)
+    }
+
+    /// Whether the connection should stay open for another request after
+    /// this one: HTTP/1.1 defaults to keeping alive unless `Connection:
+    /// close` is sent; HTTP/1.0 defaults to closing unless `Connection:
+    /// keep-alive` is sent.
+    pub fn keep_alive(&self) -> bool {
+        match self.get("Connection").map(str::to_ascii_lowercase) {
+            Some(value) if value == "close" => false,
+            Some(value) if value == "keep-alive" => true,
+            _ => self.version == "HTTP/1.1",
+        }
+    }
+}
+
+/// Errors that can occur while reading and framing an incoming request. Each
+/// variant maps to the status code a caller should answer with.
+#[derive(Debug)]
+pub enum RequestError {
+    /// The header section, a chunk-size line, or a chunked body's trailer
+    /// section exceeded `MAX_HTTP_MESSAGE_HEADER_SIZE` without its
+    /// terminator.
+    HeaderTooLarge,
+    /// The framed body exceeded the configured maximum body size.
+    BodyTooLarge,
+    /// The request line, headers, or chunk framing could not be parsed.
+    Malformed(String),
+    /// The peer closed the connection before any bytes arrived.
+    ConnectionClosed,
+    /// No bytes arrived within the configured read timeout.
+    Timeout,
+    Io(std::io::Error),
+}
+
+impl RequestError {
+    /// Status code to answer with for this error.
+    pub fn status(&self) -> u16 {
+        match self {
+            RequestError::HeaderTooLarge => 431,
+            RequestError::BodyTooLarge => 413,
+            RequestError::Malformed(_) => 400,
+            RequestError::ConnectionClosed => 400,
+            RequestError::Timeout => 408,
+            RequestError::Io(_) => 400,
+        }
+    }
+}
+
+impl From<std::io::Error> for RequestError {
+    fn from(e: std::io::Error) -> Self {
+        RequestError::Io(e)
+    }
+}
+
+impl From<httparse::Error> for RequestError {
+    fn from(e: httparse::Error) -> Self {
+        RequestError::Malformed(e.to_string())
+    }
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Reads into `chunk` with `timeout`, mapping an elapsed timeout to
+/// `RequestError::Timeout` instead of letting the caller's read hang
+/// forever on a peer that stops sending mid-message.
+pub(crate) async fn timed_read(
+    socket: &mut TcpStream,
+    chunk: &mut [u8],
+    timeout: Duration,
+) -> Result<usize, RequestError> {
+    match tokio::time::timeout(timeout, socket.read(chunk)).await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(RequestError::Timeout),
+    }
+}
+
+/// Reads from `socket`, growing `buf` (starting from any bytes already
+/// buffered in `leftover`, e.g. from a previous kept-alive request), until
+/// `httparse` can parse a complete request line and header section,
+/// enforcing `MAX_HTTP_MESSAGE_HEADER_SIZE`. Returns the parsed request
+/// (with an empty body) along with any bytes read past the header
+/// terminator, since those belong to the body.
+async fn read_request_head(
+    socket: &mut TcpStream,
+    leftover: Vec<u8>,
+    read_timeout: Duration,
+) -> Result<(HTTPRequest, Vec<u8>), RequestError> {
+    let mut buf = leftover;
+    let mut chunk = [0u8; 512];
+    loop {
+        let mut header_storage = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut parsed = httparse::Request::new(&mut header_storage);
+        match parsed.parse(&buf)? {
+            httparse::Status::Complete(offset) => {
+                let method = parsed
+                    .method
+                    .ok_or_else(|| RequestError::Malformed("missing method".to_string()))?
+                    .to_string();
+                let path = parsed
+                    .path
+                    .ok_or_else(|| RequestError::Malformed("missing path".to_string()))?
+                    .to_string();
+                let version = match parsed.version {
+                    Some(1) => "HTTP/1.1".to_string(),
+                    Some(0) => "HTTP/1.0".to_string(),
+                    _ => {
+                        return Err(RequestError::Malformed("unsupported HTTP version".to_string()))
+                    }
+                };
+                let mut headers: HashMap<String, Vec<String>> = HashMap::new();
+                for header in parsed.headers.iter() {
+                    let name = header.name.to_ascii_lowercase();
+                    let value = std::str::from_utf8(header.value)
+                        .map_err(|e| RequestError::Malformed(e.to_string()))?
+                        .trim()
+                        .to_string();
+                    headers.entry(name).or_default().push(value);
+                }
+                let leftover = buf.split_off(offset);
+                return Ok((
+                    HTTPRequest {
+                        method,
+                        path,
+                        version,
+                        headers,
+                        body: Vec::new(),
+                        path_params: HashMap::new(),
+                    },
+                    leftover,
+                ));
+            }
+            httparse::Status::Partial => {
+                if buf.len() > MAX_HTTP_MESSAGE_HEADER_SIZE {
+                    return Err(RequestError::HeaderTooLarge);
+                }
+                let n = timed_read(socket, &mut chunk, read_timeout).await?;
+                if n == 0 {
+                    return if buf.is_empty() {
+                        Err(RequestError::ConnectionClosed)
+                    } else {
+                        Err(RequestError::Malformed(
+                            "connection closed mid-headers".to_string(),
+                        ))
+                    };
+                }
+                buf.extend_from_slice(&chunk[0..n]);
+            }
+        }
+    }
+}
+
+/// Reads exactly `len` more body bytes, starting from whatever was already
+/// buffered in `leftover`, enforcing `max_body_size`. Returns the body along
+/// with any bytes read past it, which belong to the next kept-alive request.
+pub(crate) async fn read_content_length_body(
+    socket: &mut TcpStream,
+    leftover: Vec<u8>,
+    len: usize,
+    max_body_size: usize,
+    read_timeout: Duration,
+) -> Result<(Vec<u8>, Vec<u8>), RequestError> {
+    if len > max_body_size {
+        return Err(RequestError::BodyTooLarge);
+    }
+    let mut buf = leftover;
+    let mut chunk = [0u8; 4096];
+    while buf.len() < len {
+        let n = timed_read(socket, &mut chunk, read_timeout).await?;
+        if n == 0 {
+            return Err(RequestError::Malformed(
+                "connection closed before Content-Length bytes were received".to_string(),
+            ));
+        }
+        buf.extend_from_slice(&chunk[0..n]);
+    }
+    let leftover = buf.split_off(len);
+    Ok((buf, leftover))
+}
+
+/// Decodes a `Transfer-Encoding: chunked` body: repeatedly reads a hex size
+/// line, that many bytes, and the trailing CRLF, stopping at the `0`-sized
+/// chunk and consuming any trailer headers up to the final blank line.
+/// Returns the body along with any bytes read past the terminator, which
+/// belong to the next kept-alive request.
+pub(crate) async fn read_chunked_body(
+    socket: &mut TcpStream,
+    leftover: Vec<u8>,
+    max_body_size: usize,
+    read_timeout: Duration,
+) -> Result<(Vec<u8>, Vec<u8>), RequestError> {
+    let mut buf = leftover;
+    let mut body = Vec::new();
+    loop {
+        let size_line_end = loop {
+            if let Some(pos) = find_subslice(&buf, b"\r\n") {
+                break pos;
+            }
+            if buf.len() > MAX_HTTP_MESSAGE_HEADER_SIZE {
+                return Err(RequestError::HeaderTooLarge);
+            }
+            read_more(socket, &mut buf, read_timeout).await?;
+        };
+        let size_line = std::str::from_utf8(&buf[0..size_line_end])
+            .map_err(|e| RequestError::Malformed(e.to_string()))?;
+        // Chunk extensions (after `;`) are accepted but ignored.
+        let size_str = size_line.split(';').next().unwrap_or(size_line).trim();
+        let chunk_size = usize::from_str_radix(size_str, 16)
+            .map_err(|_| RequestError::Malformed(format!("invalid chunk size: {size_str}")))?;
+
+        if chunk_size == 0 {
+            // The last-chunk's own CRLF (the one size_line_end just found) is
+            // the first half of the blank-line terminator when there are no
+            // trailer headers, so search for it from the start of buf rather
+            // than draining the size line first.
+            loop {
+                if let Some(pos) = find_subslice(&buf, b"\r\n\r\n") {
+                    let leftover = buf.split_off(pos + 4);
+                    return Ok((body, leftover));
+                }
+                if buf.len() > MAX_HTTP_MESSAGE_HEADER_SIZE {
+                    return Err(RequestError::HeaderTooLarge);
+                }
+                read_more(socket, &mut buf, read_timeout).await?;
+            }
+        }
+        buf.drain(0..size_line_end + 2);
+
+        if body.len() + chunk_size > max_body_size {
+            return Err(RequestError::BodyTooLarge);
+        }
+
+        while buf.len() < chunk_size + 2 {
+            read_more(socket, &mut buf, read_timeout).await?;
+        }
+        body.extend_from_slice(&buf[0..chunk_size]);
+        buf.drain(0..chunk_size + 2);
+    }
+}
+
+async fn read_more(
+    socket: &mut TcpStream,
+    buf: &mut Vec<u8>,
+    read_timeout: Duration,
+) -> Result<(), RequestError> {
+    let mut chunk = [0u8; 4096];
+    let n = timed_read(socket, &mut chunk, read_timeout).await?;
+    if n == 0 {
+        return Err(RequestError::Malformed(
+            "connection closed mid-chunked-body".to_string(),
+        ));
+    }
+    buf.extend_from_slice(&chunk[0..n]);
+    Ok(())
+}
+
+/// Reads one full HTTP request (headers plus framed body) from `socket`,
+/// honoring `Content-Length` and `Transfer-Encoding: chunked`, starting from
+/// any bytes already buffered in `leftover` (e.g. from a previous kept-alive
+/// request on the same connection). Returns the request along with any
+/// bytes read past it, to be threaded into the next call on this
+/// connection.
+pub async fn read_request(
+    socket: &mut TcpStream,
+    leftover: Vec<u8>,
+    max_body_size: usize,
+    read_timeout: Duration,
+) -> Result<(HTTPRequest, Vec<u8>), RequestError> {
+    let (mut request, leftover) = read_request_head(socket, leftover, read_timeout).await?;
+
+    let (body, leftover) = if request.is_chunked() {
+        read_chunked_body(socket, leftover, max_body_size, read_timeout).await?
+    } else if let Some(len) = request.content_length() {
+        read_content_length_body(socket, leftover, len, max_body_size, read_timeout).await?
+    } else {
+        (Vec::new(), leftover)
+    };
+    request.body = body;
+
+    Ok((request, leftover))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    /// A connected in-process `TcpStream` pair, so the framing helpers below
+    /// can be exercised against a real socket instead of a mock.
+    async fn socket_pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = TcpStream::connect(addr).await.unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+        (client, server)
+    }
+
+    #[tokio::test]
+    async fn content_length_body_splits_off_leftover_bytes() {
+        let (mut client, mut server) = socket_pair().await;
+        client.write_all(b"hello world NEXT").await.unwrap();
+
+        let (body, leftover) = read_content_length_body(
+            &mut server,
+            Vec::new(),
+            "hello".len(),
+            DEFAULT_MAX_BODY_SIZE,
+            DEFAULT_READ_TIMEOUT,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(body, b"hello");
+        assert_eq!(leftover, b" world NEXT");
+    }
+
+    #[tokio::test]
+    async fn chunked_body_decodes_and_returns_leftover_bytes() {
+        let (mut client, mut server) = socket_pair().await;
+        client
+            .write_all(b"5\r\nhello\r\n0\r\n\r\nNEXT")
+            .await
+            .unwrap();
+
+        let (body, leftover) = read_chunked_body(
+            &mut server,
+            Vec::new(),
+            DEFAULT_MAX_BODY_SIZE,
+            DEFAULT_READ_TIMEOUT,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(body, b"hello");
+        assert_eq!(leftover, b"NEXT");
+    }
+
+    #[tokio::test]
+    async fn chunked_body_with_no_terminator_fails_instead_of_growing_forever() {
+        let (mut client, mut server) = socket_pair().await;
+        let oversized_line = "f".repeat(MAX_HTTP_MESSAGE_HEADER_SIZE + 1);
+        client.write_all(oversized_line.as_bytes()).await.unwrap();
+        drop(client);
+
+        let result = read_chunked_body(
+            &mut server,
+            Vec::new(),
+            DEFAULT_MAX_BODY_SIZE,
+            DEFAULT_READ_TIMEOUT,
+        )
+        .await;
+
+        assert!(matches!(result, Err(RequestError::HeaderTooLarge)));
+    }
+
+    #[tokio::test]
+    async fn read_with_no_bytes_before_timeout_fails_with_timeout_error() {
+        let (_client, mut server) = socket_pair().await;
+
+        let result = read_content_length_body(
+            &mut server,
+            Vec::new(),
+            "hello".len(),
+            DEFAULT_MAX_BODY_SIZE,
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert!(matches!(result, Err(RequestError::Timeout)));
+    }
+
+    #[test]
+    fn duplicate_headers_are_kept_as_a_list() {
+        let mut request = HTTPRequest {
+            method: "GET".to_string(),
+            path: "/".to_string(),
+            version: "HTTP/1.1".to_string(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+            path_params: HashMap::new(),
+        };
+        request
+            .headers
+            .entry("set-cookie".to_string())
+            .or_default()
+            .extend(["a=1".to_string(), "b=2".to_string()]);
+
+        assert_eq!(request.get_all("Set-Cookie"), ["a=1", "b=2"]);
+        assert_eq!(request.get("Set-Cookie"), Some("a=1"));
+    }
+
+    #[test]
+    fn keep_alive_defaults_follow_the_http_version() {
+        let mut request = HTTPRequest {
+            method: "GET".to_string(),
+            path: "/".to_string(),
+            version: "HTTP/1.1".to_string(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+            path_params: HashMap::new(),
+        };
+        assert!(request.keep_alive());
+
+        request.version = "HTTP/1.0".to_string();
+        assert!(!request.keep_alive());
+
+        request
+            .headers
+            .insert("connection".to_string(), vec!["keep-alive".to_string()]);
+        assert!(request.keep_alive());
+
+        request
+            .headers
+            .insert("connection".to_string(), vec!["close".to_string()]);
+        assert!(!request.keep_alive());
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::request::RequestError;
+
+/// The body of an `HTTPResponse`, either fully buffered or streamed as
+/// `Transfer-Encoding: chunked` segments.
+#[derive(Debug)]
+pub enum ResponseBody {
+    Buffered(Vec<u8>),
+    Chunked(Vec<Vec<u8>>),
+}
+
+/// A response ready to be written to the wire.
+#[derive(Debug)]
+pub struct HTTPResponse {
+    pub http_version: &'static str,
+    pub status_code: u16,
+    pub reason_phrase: String,
+    pub headers: HashMap<String, String>,
+    pub body: ResponseBody,
+}
+
+impl HTTPResponse {
+    /// Serializes the status line, headers, and body into wire bytes. A
+    /// chunked body is emitted as size-prefixed segments terminated by the
+    /// `0\r\n\r\n` final chunk; empty segments are skipped rather than
+    /// emitted as zero-sized (and therefore terminating) wire chunks.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut serialized = format!(
+            "{} {} {}\r\n",
+            self.http_version, self.status_code, self.reason_phrase
+        )
+        .into_bytes();
+        for (name, value) in self.headers.iter() {
+            serialized.extend_from_slice(format!("{name}: {value}\r\n").as_bytes());
+        }
+        serialized.extend_from_slice(b"\r\n");
+        match &self.body {
+            ResponseBody::Buffered(bytes) => serialized.extend_from_slice(bytes),
+            ResponseBody::Chunked(segments) => {
+                for segment in segments.iter().filter(|segment| !segment.is_empty()) {
+                    serialized.extend_from_slice(format!("{:x}\r\n", segment.len()).as_bytes());
+                    serialized.extend_from_slice(segment);
+                    serialized.extend_from_slice(b"\r\n");
+                }
+                serialized.extend_from_slice(b"0\r\n\r\n");
+            }
+        }
+        serialized
+    }
+}
+
+/// Builds an `HTTPResponse`, filling in sensible defaults (HTTP/1.1, a
+/// reason phrase derived from the status code, an empty header map, and an
+/// empty body) so callers only need to set what's unusual.
+pub struct HTTPResponseBuilder {
+    status_code: u16,
+    reason_phrase: String,
+    headers: HashMap<String, String>,
+}
+
+impl HTTPResponseBuilder {
+    pub fn new(status_code: u16) -> Self {
+        HTTPResponseBuilder {
+            status_code,
+            reason_phrase: default_reason_phrase(status_code).to_string(),
+            headers: HashMap::new(),
+        }
+    }
+
+    pub fn reason_phrase(mut self, reason_phrase: impl Into<String>) -> Self {
+        self.reason_phrase = reason_phrase.into();
+        self
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn body(self, body: impl Into<Vec<u8>>) -> HTTPResponse {
+        let body = body.into();
+        let mut headers = self.headers;
+        headers
+            .entry("Content-Length".to_string())
+            .or_insert_with(|| body.len().to_string());
+        HTTPResponse {
+            http_version: "HTTP/1.1",
+            status_code: self.status_code,
+            reason_phrase: self.reason_phrase,
+            headers,
+            body: ResponseBody::Buffered(body),
+        }
+    }
+
+    pub fn chunked_body(self, segments: impl IntoIterator<Item = Vec<u8>>) -> HTTPResponse {
+        let mut headers = self.headers;
+        headers
+            .entry("Transfer-Encoding".to_string())
+            .or_insert_with(|| "chunked".to_string());
+        HTTPResponse {
+            http_version: "HTTP/1.1",
+            status_code: self.status_code,
+            reason_phrase: self.reason_phrase,
+            headers,
+            body: ResponseBody::Chunked(segments.into_iter().collect()),
+        }
+    }
+
+    pub fn empty(self) -> HTTPResponse {
+        self.body(Vec::new())
+    }
+}
+
+fn default_reason_phrase(status_code: u16) -> &'static str {
+    match status_code {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        _ => "",
+    }
+}
+
+/// Builds an error response for a failed request read, using the error's
+/// own status code as the response status.
+pub fn error_response(error: &RequestError) -> HTTPResponse {
+    HTTPResponseBuilder::new(error.status()).empty()
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::request::HTTPRequest;
+use crate::response::{HTTPResponse, HTTPResponseBuilder};
+
+/// A boxed, pinned future, used so `Router` can store handlers of different
+/// concrete future types behind one trait object.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Something that can answer an `HTTPRequest` with an `HTTPResponse`.
+/// Implemented for any `Fn(HTTPRequest) -> impl Future<Output = HTTPResponse>`
+/// closure, so ordinary `async fn`/async closures can be registered directly
+/// without implementing the trait by hand.
+pub trait Handler: Send + Sync {
+    fn call(&self, request: HTTPRequest) -> BoxFuture<'static, HTTPResponse>;
+}
+
+impl<F, Fut> Handler for F
+where
+    F: Fn(HTTPRequest) -> Fut + Send + Sync,
+    Fut: Future<Output = HTTPResponse> + Send + 'static,
+{
+    fn call(&self, request: HTTPRequest) -> BoxFuture<'static, HTTPResponse> {
+        Box::pin(self(request))
+    }
+}
+
+/// One segment of a parsed route pattern.
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Param(String),
+}
+
+fn parse_pattern(pattern: &str) -> Vec<Segment> {
+    pattern
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => Segment::Param(name.to_string()),
+            None => Segment::Literal(segment.to_string()),
+        })
+        .collect()
+}
+
+/// Matches `path` against `pattern`, returning the captured `:name` params
+/// on success.
+fn match_pattern(pattern: &[Segment], path: &str) -> Option<HashMap<String, String>> {
+    let path_segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if pattern.len() != path_segments.len() {
+        return None;
+    }
+    let mut params = HashMap::new();
+    for (segment, value) in pattern.iter().zip(path_segments.iter()) {
+        match segment {
+            Segment::Literal(literal) => {
+                if literal != value {
+                    return None;
+                }
+            }
+            Segment::Param(name) => {
+                params.insert(name.clone(), value.to_string());
+            }
+        }
+    }
+    Some(params)
+}
+
+struct Route {
+    method: String,
+    pattern: Vec<Segment>,
+    handler: Box<dyn Handler>,
+}
+
+/// Dispatches requests to registered handlers by method and path pattern
+/// (`/users/:id`-style segments), falling back to 404/405 handlers when
+/// nothing matches.
+pub struct Router {
+    routes: Vec<Route>,
+    not_found: Box<dyn Handler>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Router {
+            routes: Vec::new(),
+            not_found: Box::new(not_found_handler),
+        }
+    }
+
+    /// Registers `handler` for `method` requests whose path matches
+    /// `pattern`.
+    pub fn add(mut self, method: &str, pattern: &str, handler: impl Handler + 'static) -> Self {
+        self.routes.push(Route {
+            method: method.to_ascii_uppercase(),
+            pattern: parse_pattern(pattern),
+            handler: Box::new(handler),
+        });
+        self
+    }
+
+    /// Overrides the handler used when no route's pattern matches the path
+    /// at all (the default answers `404 Not Found`).
+    pub fn set_not_found(mut self, handler: impl Handler + 'static) -> Self {
+        self.not_found = Box::new(handler);
+        self
+    }
+
+    /// Finds the route matching `request`'s path and method and calls its
+    /// handler, filling in `request.path_params` from the match. If the
+    /// path matches some route but not with this method, answers `405
+    /// Method Not Allowed`; if no route's path matches at all, falls back to
+    /// the not-found handler.
+    pub async fn dispatch(&self, mut request: HTTPRequest) -> HTTPResponse {
+        let mut path_matched = false;
+        for route in &self.routes {
+            let Some(params) = match_pattern(&route.pattern, &request.path) else {
+                continue;
+            };
+            path_matched = true;
+            if route.method != request.method {
+                continue;
+            }
+            request.path_params = params;
+            return route.handler.call(request).await;
+        }
+        if path_matched {
+            method_not_allowed_handler(request).await
+        } else {
+            self.not_found.call(request).await
+        }
+    }
+}
+
+impl Default for Router {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn not_found_handler(_request: HTTPRequest) -> HTTPResponse {
+    HTTPResponseBuilder::new(404).empty()
+}
+
+async fn method_not_allowed_handler(_request: HTTPRequest) -> HTTPResponse {
+    HTTPResponseBuilder::new(405).empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn request(method: &str, path: &str) -> HTTPRequest {
+        HTTPRequest {
+            method: method.to_string(),
+            path: path.to_string(),
+            version: "HTTP/1.1".to_string(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+            path_params: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn dispatches_to_matching_route_and_captures_path_params() {
+        let router = Router::new().add("GET", "/users/:id", |request: HTTPRequest| async move {
+            HTTPResponseBuilder::new(200).body(request.path_params["id"].clone())
+        });
+
+        let response = router.dispatch(request("GET", "/users/42")).await;
+
+        assert_eq!(response.status_code, 200);
+        assert!(matches!(
+            response.body,
+            crate::response::ResponseBody::Buffered(bytes) if bytes == b"42"
+        ));
+    }
+
+    #[tokio::test]
+    async fn unmatched_path_falls_back_to_404() {
+        let router = Router::new().add("GET", "/users/:id", |_: HTTPRequest| async move {
+            HTTPResponseBuilder::new(200).empty()
+        });
+
+        let response = router.dispatch(request("GET", "/nope")).await;
+
+        assert_eq!(response.status_code, 404);
+    }
+
+    #[tokio::test]
+    async fn matched_path_with_wrong_method_is_405() {
+        let router = Router::new().add("GET", "/users/:id", |_: HTTPRequest| async move {
+            HTTPResponseBuilder::new(200).empty()
+        });
+
+        let response = router.dispatch(request("DELETE", "/users/42")).await;
+
+        assert_eq!(response.status_code, 405);
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::request::{read_request, DEFAULT_MAX_BODY_SIZE, DEFAULT_READ_TIMEOUT};
+use crate::response::error_response;
+use crate::router::{Handler, Router};
+
+/// How long a connection waits for the next request's first byte; the wait
+/// is retried once, so an idle connection is closed after up to twice this
+/// duration. Unlike `DEFAULT_READ_TIMEOUT`,
+/// timing out here isn't an error: it just means the client is done with
+/// this connection.
+pub const DEFAULT_TIME_TO_FIRST_BYTE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A minimal HTTP server: binds a listener, routes each request through a
+/// `Router`, and writes back whatever `HTTPResponse` the matched handler
+/// produces. Connections are kept alive across requests per
+/// `HTTPRequest::keep_alive`.
+pub struct Server {
+    addr: String,
+    router: Router,
+    max_body_size: usize,
+    read_timeout: Duration,
+    time_to_first_byte_timeout: Duration,
+}
+
+impl Server {
+    /// Starts building a server that will bind to `addr` once `run` is
+    /// called.
+    pub fn bind(addr: impl Into<String>) -> Self {
+        Server {
+            addr: addr.into(),
+            router: Router::new(),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            time_to_first_byte_timeout: DEFAULT_TIME_TO_FIRST_BYTE_TIMEOUT,
+        }
+    }
+
+    /// Registers `handler` for `method` requests matching `pattern`.
+    pub fn route(mut self, method: &str, pattern: &str, handler: impl Handler + 'static) -> Self {
+        self.router = self.router.add(method, pattern, handler);
+        self
+    }
+
+    /// Overrides the handler used when no route matches the request's path.
+    pub fn fallback(mut self, handler: impl Handler + 'static) -> Self {
+        self.router = self.router.set_not_found(handler);
+        self
+    }
+
+    /// Overrides the maximum accepted request body size.
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Overrides how long a single read may take once a request has started
+    /// arriving.
+    pub fn read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    /// Overrides how long a connection waits for the next request's first
+    /// byte. The wait is retried once, so an idle connection is closed after
+    /// up to twice `timeout`.
+    pub fn time_to_first_byte_timeout(mut self, timeout: Duration) -> Self {
+        self.time_to_first_byte_timeout = timeout;
+        self
+    }
+
+    /// Binds the listener and serves connections until accepting a
+    /// connection fails, handling each connection on its own task so a slow
+    /// client can't stall the others.
+    pub async fn run(self) -> std::io::Result<()> {
+        let listener = TcpListener::bind(&self.addr).await?;
+        let router = Arc::new(self.router);
+        let max_body_size = self.max_body_size;
+        let read_timeout = self.read_timeout;
+        let time_to_first_byte_timeout = self.time_to_first_byte_timeout;
+        loop {
+            let (socket, _) = listener.accept().await?;
+            let router = Arc::clone(&router);
+            tokio::spawn(async move {
+                Self::serve_connection(
+                    socket,
+                    &router,
+                    max_body_size,
+                    read_timeout,
+                    time_to_first_byte_timeout,
+                )
+                .await;
+            });
+        }
+    }
+
+    /// Serves requests on `socket` one after another for as long as both
+    /// sides want to keep the connection alive, threading any bytes read
+    /// past one request's end into the next request's read.
+    async fn serve_connection(
+        mut socket: TcpStream,
+        router: &Router,
+        max_body_size: usize,
+        read_timeout: Duration,
+        time_to_first_byte_timeout: Duration,
+    ) {
+        let mut leftover = Vec::new();
+        loop {
+            if leftover.is_empty()
+                && !wait_for_first_byte(&mut socket, time_to_first_byte_timeout).await
+            {
+                return;
+            }
+
+            let (response, keep_alive) =
+                match read_request(&mut socket, std::mem::take(&mut leftover), max_body_size, read_timeout)
+                    .await
+                {
+                    Ok((request, new_leftover)) => {
+                        leftover = new_leftover;
+                        let keep_alive = request.keep_alive();
+                        (router.dispatch(request).await, keep_alive)
+                    }
+                    Err(error) => (error_response(&error), false),
+                };
+
+            if tokio::io::AsyncWriteExt::write_all(&mut socket, &response.serialize())
+                .await
+                .is_err()
+                || !keep_alive
+            {
+                return;
+            }
+        }
+    }
+}
+
+/// Waits up to `timeout` for the next request's first byte to become
+/// readable and, if the wait times out, waits once more for the same
+/// duration before giving up, so an idle connection is closed after at most
+/// twice `timeout`. Returns `false` if no data arrived after the retry,
+/// meaning the connection should be closed as idle rather than treated as
+/// an error.
+async fn wait_for_first_byte(socket: &mut TcpStream, timeout: Duration) -> bool {
+    for _ in 0..2 {
+        if tokio::time::timeout(timeout, socket.readable())
+            .await
+            .is_ok()
+        {
+            return true;
+        }
+    }
+    false
+}
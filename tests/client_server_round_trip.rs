@@ -0,0 +1,35 @@
+use rust_toy_http_server::{get, post, HTTPRequest, HTTPResponseBuilder, Server};
+
+async fn echo(request: HTTPRequest) -> rust_toy_http_server::HTTPResponse {
+    HTTPResponseBuilder::new(200).body(request.body)
+}
+
+#[tokio::test]
+async fn client_round_trips_get_and_post_against_the_server() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    tokio::spawn(async move {
+        Server::bind(addr.to_string())
+            .fallback(echo)
+            .run()
+            .await
+            .unwrap();
+    });
+
+    // Give the server a moment to bind before the client connects.
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    let response = get(&format!("http://{addr}/")).await.unwrap();
+    assert_eq!(response.status_code, 200);
+
+    let response = post(&format!("http://{addr}/"), b"hello").await.unwrap();
+    assert_eq!(response.status_code, 200);
+    match response.body {
+        rust_toy_http_server::response::ResponseBody::Buffered(bytes) => {
+            assert_eq!(bytes, b"hello")
+        }
+        _ => panic!("expected a buffered response body"),
+    }
+}